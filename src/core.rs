@@ -1,13 +1,15 @@
 use anyhow::Result;
 use console::style;
+use ignore::WalkBuilder;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
+use std::collections::HashMap;
 use std::fs;
-use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
-use walkdir::WalkDir;
 
+use crate::output::{FileEntry, OutputFormat, OutputWriter};
 use crate::perf::Timer;
 use crate::utils;
 
@@ -17,6 +19,11 @@ pub fn convert_repository_to_text(
     output_path: &str,
     threshold_mb: f64,
     include_all: bool,
+    no_ignore: bool,
+    file_filters: &utils::FileFilters,
+    format: OutputFormat,
+    threads: usize,
+    tree: bool,
     debug: bool,
     profile: bool,
 ) -> Result<()> {
@@ -26,10 +33,19 @@ pub fn convert_repository_to_text(
         None
     };
 
-    // Use buffered writer for better I/O performance
-    let file = fs::File::create(output_path)?;
-    let output_file = Mutex::new(BufWriter::new(file));
+    if threads > 0 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .map_err(|e| anyhow::anyhow!("Failed to configure thread pool: {}", e))?;
+    }
+    let thread_count = rayon::current_num_threads();
+
     let threshold_bytes = (threshold_mb * 1024.0 * 1024.0) as u64;
+    let io_ops = AtomicU64::new(0);
+
+    // Create the output file up front so a "no files found" run still produces one
+    let file = fs::File::create(output_path)?;
 
     // Optimized file discovery with pre-filtering
     let discovery_timer = if profile {
@@ -38,13 +54,58 @@ pub fn convert_repository_to_text(
         None
     };
 
-    let files: Vec<_> = WalkDir::new(repo_path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter(|e| !utils::should_exclude_file(e.path()))
-        .map(|e| e.path().to_path_buf())
-        .collect();
+    let files = Mutex::new(Vec::new());
+
+    let mut walker = WalkBuilder::new(repo_path);
+    walker
+        .git_ignore(!no_ignore)
+        .git_exclude(!no_ignore)
+        .git_global(!no_ignore)
+        .ignore(!no_ignore)
+        .parents(!no_ignore)
+        .hidden(false);
+
+    walker.build_parallel().run(|| {
+        Box::new(|entry| {
+            use ignore::WalkState;
+
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => return WalkState::Continue,
+            };
+
+            let is_file = entry.file_type().map(|ft| ft.is_file()).unwrap_or(false);
+            if !is_file || utils::is_excluded_directory(entry.path()) {
+                return WalkState::Continue;
+            }
+
+            // An explicit --include-ext is the user asking for that extension by name,
+            // so it overrides the default binary-extension skip list (but not the
+            // directory exclusions above, and --exclude-ext/--exclude-glob still apply
+            // via file_filters.should_exclude below)
+            let bypasses_binary_list = file_filters.has_include_ext();
+            if !bypasses_binary_list && utils::is_excluded_extension(entry.path()) {
+                return WalkState::Continue;
+            }
+
+            if file_filters.should_exclude(entry.path()) {
+                return WalkState::Continue;
+            }
+
+            if debug && bypasses_binary_list && utils::is_excluded_extension(entry.path()) {
+                println!(
+                    "Including {} despite default binary-extension exclusion (matched --include-ext)",
+                    entry.path().display()
+                );
+            }
+
+            files.lock().unwrap().push(entry.path().to_path_buf());
+
+            WalkState::Continue
+        })
+    });
+
+    let files: Vec<PathBuf> = files.into_inner().unwrap();
 
     if let Some(timer) = discovery_timer {
         timer.print_elapsed();
@@ -55,6 +116,35 @@ pub fn convert_repository_to_text(
         return Ok(());
     }
 
+    // When --tree is requested we need to know, before writing a single byte, which
+    // files survive the binary/threshold filter. Inspect every file up front once and
+    // reuse those results in the main loop below instead of inspecting twice.
+    let inspections = if tree {
+        Some(inspect_all(&files, &io_ops)?)
+    } else {
+        None
+    };
+
+    let tree_header = inspections.as_ref().map(|inspections| {
+        let included: Vec<(PathBuf, u64)> = files
+            .iter()
+            .filter_map(|file_path| {
+                let inspection = inspections.get(file_path)?;
+                let should_skip =
+                    !include_all && (inspection.is_binary || inspection.size > threshold_bytes);
+                if should_skip {
+                    return None;
+                }
+                let relative_path = file_path.strip_prefix(repo_path).ok()?.to_path_buf();
+                Some((relative_path, inspection.size))
+            })
+            .collect();
+
+        crate::tree::render(&included)
+    });
+
+    let output_writer = OutputWriter::new(file, format, tree_header.as_deref())?;
+
     let pb = ProgressBar::new(files.len() as u64);
     pb.set_style(
         ProgressStyle::default_bar()
@@ -75,18 +165,38 @@ pub fn convert_repository_to_text(
     };
 
     files.par_iter().try_for_each(|file_path| -> Result<()> {
-        let metadata = fs::metadata(file_path)?;
-        let file_size = metadata.len();
+        // If the --tree pass already inspected this file, reuse that result instead of
+        // sniffing its header a second time; otherwise inspect it now (the common case)
+        let (file_size, is_binary, detected_type, already_open) = match &inspections {
+            Some(cache) => {
+                let inspection = cache
+                    .get(file_path)
+                    .expect("every discovered file was inspected during the tree pass");
+                (inspection.size, inspection.is_binary, inspection.detected_type.clone(), None)
+            }
+            None => {
+                let (inspection, sniff_buf, file) = utils::inspect_file(file_path)?;
+                io_ops.fetch_add(1, Ordering::Relaxed);
+                (inspection.size, inspection.is_binary, inspection.detected_type, Some((sniff_buf, file)))
+            }
+        };
         let relative_path = file_path.strip_prefix(repo_path)?;
 
         pb.set_message(format!("Processing: {}", relative_path.display()));
 
         // Check if file should be included
         let should_skip = if !include_all {
-            // Skip binary files (optimized check)
-            if utils::is_binary_file(file_path)? {
+            // Skip binary files (content-sniffed via magic bytes)
+            if is_binary {
                 if debug {
-                    println!("Skipping binary file: {}", relative_path.display());
+                    match &detected_type {
+                        Some(mime_type) => println!(
+                            "Skipping binary file: {} (detected type: {})",
+                            relative_path.display(),
+                            mime_type
+                        ),
+                        None => println!("Skipping binary file: {}", relative_path.display()),
+                    }
                 }
                 true
             }
@@ -113,36 +223,31 @@ pub fn convert_repository_to_text(
             return Ok(());
         }
 
-        // Process and write file content
-        let mut content = String::new();
-
-        // Build content string first
-        content.push_str(&"=".repeat(80));
-        content.push('\n');
-        content.push_str(&format!("File: {}\n", relative_path.display()));
-        content.push_str(&format!("Size: {}\n", utils::format_file_size(file_size)));
-        content.push_str(&"=".repeat(80));
-        content.push_str("\n\n");
-
-        // Read file content efficiently
-        match utils::read_file_content(file_path, file_size) {
-            Ok(file_contents) => {
-                content.push_str(&file_contents);
+        // Read the rest of the file: on the handle the sniff already opened if we have
+        // one, otherwise a fresh open (the tree pass didn't keep its handle around)
+        let read_result = match already_open {
+            Some((sniff_buf, file)) => utils::finish_reading(file, sniff_buf, file_size),
+            None => {
+                io_ops.fetch_add(1, Ordering::Relaxed);
+                utils::read_file_content(file_path, file_size)
             }
+        };
+
+        let content = match read_result {
+            Ok(file_contents) => file_contents,
             Err(_) => {
-                content.push_str("[Binary file or read error]");
                 if debug {
                     println!("Could not read file as text: {}", relative_path.display());
                 }
+                "[Binary file or read error]".to_string()
             }
-        }
-        content.push_str("\n\n");
+        };
 
-        // Write to output file (synchronized)
-        {
-            let mut writer = output_file.lock().unwrap();
-            writer.write_all(content.as_bytes())?;
-        }
+        output_writer.write_entry(FileEntry {
+            path: relative_path,
+            size_bytes: file_size,
+            content: &content,
+        })?;
 
         *processed_files.lock().unwrap() += 1;
         pb.inc(1);
@@ -153,17 +258,17 @@ pub fn convert_repository_to_text(
         timer.print_elapsed();
     }
 
-    // Ensure all data is written
+    let processed_count = *processed_files.lock().unwrap();
+    let skipped_count = *skipped_files.lock().unwrap();
+
+    // Ensure all data is written (and, for JSON, the summary footer is appended)
     let flush_timer = if profile {
         Some(Timer::new("File flush"))
     } else {
         None
     };
 
-    {
-        let mut writer = output_file.lock().unwrap();
-        writer.flush()?;
-    }
+    output_writer.finish(skipped_count)?;
 
     if let Some(timer) = flush_timer {
         timer.print_elapsed();
@@ -171,18 +276,36 @@ pub fn convert_repository_to_text(
 
     pb.finish_with_message("Conversion complete!");
 
-    let processed_count = *processed_files.lock().unwrap();
-    let skipped_count = *skipped_files.lock().unwrap();
-
     println!(
         "ðŸ“Š Processed {} files, skipped {} files",
         style(processed_count.to_string()).green(),
         style(skipped_count.to_string()).yellow()
     );
 
+    if profile {
+        println!("Threads used: {}", thread_count);
+        println!("I/O operations: {}", io_ops.load(Ordering::Relaxed));
+    }
+
     if let Some(timer) = total_timer {
         timer.print_elapsed();
     }
 
     Ok(())
 }
+
+/// Inspect every discovered file once, up front, so the `--tree` header can be built
+/// (and written) before any file entry, without the main loop inspecting again
+fn inspect_all(files: &[PathBuf], io_ops: &AtomicU64) -> Result<HashMap<PathBuf, utils::FileInspection>> {
+    let inspections: Mutex<Vec<(PathBuf, utils::FileInspection)>> =
+        Mutex::new(Vec::with_capacity(files.len()));
+
+    files.par_iter().try_for_each(|file_path| -> Result<()> {
+        let (inspection, _sniff_buf, _file) = utils::inspect_file(file_path)?;
+        io_ops.fetch_add(1, Ordering::Relaxed);
+        inspections.lock().unwrap().push((file_path.clone(), inspection));
+        Ok(())
+    })?;
+
+    Ok(inspections.into_inner().unwrap().into_iter().collect())
+}
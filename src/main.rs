@@ -5,8 +5,12 @@ use std::env;
 use std::path::PathBuf;
 
 mod core;
+mod output;
+mod tree;
 mod utils;
 
+use output::OutputFormat;
+
 #[derive(Parser)]
 #[command(
     name = "textify",
@@ -31,9 +35,42 @@ struct Args {
     #[arg(long)]
     include_all: bool,
 
+    /// Disable .gitignore/.ignore/git-exclude filtering (the built-in directory and
+    /// binary-extension exclusions and the --include-ext/--exclude-* filters still apply)
+    #[arg(long)]
+    no_ignore: bool,
+
+    /// Only include files with these extensions (comma-separated, repeatable)
+    #[arg(long, value_delimiter = ',')]
+    include_ext: Vec<String>,
+
+    /// Exclude files with these extensions (comma-separated, repeatable)
+    #[arg(long, value_delimiter = ',')]
+    exclude_ext: Vec<String>,
+
+    /// Exclude files matching these glob patterns (comma-separated, repeatable)
+    #[arg(long, value_delimiter = ',')]
+    exclude_glob: Vec<String>,
+
+    /// Output document format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    /// Number of worker threads to use (0 = auto-detect available cores)
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+
+    /// Prepend a directory-tree summary of the included files before their contents
+    #[arg(long)]
+    tree: bool,
+
     /// Enable debug mode with verbose logging
     #[arg(long)]
     debug: bool,
+
+    /// Print timing information for each stage of the conversion
+    #[arg(long)]
+    profile: bool,
 }
 
 fn main() -> Result<()> {
@@ -77,12 +114,21 @@ fn main() -> Result<()> {
         style(format!("Processing repository: {}", repo_name)).green()
     );
 
+    let file_filters =
+        utils::FileFilters::new(&args.include_ext, &args.exclude_ext, &args.exclude_glob)?;
+
     core::convert_repository_to_text(
         &repo_path,
         &output_path,
         args.threshold,
         args.include_all,
+        args.no_ignore,
+        &file_filters,
+        args.format,
+        args.threads,
+        args.tree,
         args.debug,
+        args.profile,
     )?;
 
     println!(
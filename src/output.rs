@@ -0,0 +1,197 @@
+use anyhow::Result;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::utils;
+
+/// Output document format for the converted repository
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Markdown,
+}
+
+/// A single file ready to be written to the output document
+pub struct FileEntry<'a> {
+    pub path: &'a Path,
+    pub size_bytes: u64,
+    pub content: &'a str,
+}
+
+struct WriterState {
+    writer: BufWriter<File>,
+    wrote_any: bool,
+    total_files: u64,
+    total_bytes: u64,
+}
+
+/// Streams file entries to disk in the requested format. Writes are synchronized
+/// through a single mutex so callers can call `write_entry` from a rayon `par_iter`.
+pub struct OutputWriter {
+    format: OutputFormat,
+    state: Mutex<WriterState>,
+}
+
+impl OutputWriter {
+    /// `tree` is the rendered directory-tree header (see `crate::tree::render`),
+    /// written ahead of the per-file entries when `--tree` is passed
+    pub fn new(file: File, format: OutputFormat, tree: Option<&str>) -> Result<Self> {
+        let mut writer = BufWriter::new(file);
+
+        match format {
+            OutputFormat::Json => {
+                writer.write_all(b"{\n")?;
+                if let Some(tree) = tree {
+                    writeln!(writer, "  \"tree\": {},", serde_json::to_string(tree)?)?;
+                }
+                writer.write_all(b"  \"files\": [\n")?;
+            }
+            OutputFormat::Markdown => {
+                if let Some(tree) = tree {
+                    let fence = fence_for(tree);
+                    writeln!(writer, "## Repository structure\n\n{}", fence)?;
+                    writer.write_all(tree.as_bytes())?;
+                    if !tree.ends_with('\n') {
+                        writer.write_all(b"\n")?;
+                    }
+                    writeln!(writer, "{}\n", fence)?;
+                }
+            }
+            OutputFormat::Text => {
+                if let Some(tree) = tree {
+                    writer.write_all(tree.as_bytes())?;
+                    if !tree.ends_with('\n') {
+                        writer.write_all(b"\n")?;
+                    }
+                    writer.write_all(b"\n")?;
+                }
+            }
+        }
+
+        Ok(Self {
+            format,
+            state: Mutex::new(WriterState {
+                writer,
+                wrote_any: false,
+                total_files: 0,
+                total_bytes: 0,
+            }),
+        })
+    }
+
+    pub fn write_entry(&self, entry: FileEntry) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+
+        match self.format {
+            OutputFormat::Text => {
+                let writer = &mut state.writer;
+                writer.write_all("=".repeat(80).as_bytes())?;
+                writer.write_all(b"\n")?;
+                writeln!(writer, "File: {}", entry.path.display())?;
+                writeln!(writer, "Size: {}", utils::format_file_size(entry.size_bytes))?;
+                writer.write_all("=".repeat(80).as_bytes())?;
+                writer.write_all(b"\n\n")?;
+                writer.write_all(entry.content.as_bytes())?;
+                writer.write_all(b"\n\n")?;
+            }
+            OutputFormat::Markdown => {
+                let writer = &mut state.writer;
+                let fence = fence_for(entry.content);
+                writeln!(writer, "## {}\n", entry.path.display())?;
+                writeln!(writer, "{}{}", fence, language_for(entry.path))?;
+                writer.write_all(entry.content.as_bytes())?;
+                if !entry.content.ends_with('\n') {
+                    writer.write_all(b"\n")?;
+                }
+                writeln!(writer, "{}\n", fence)?;
+            }
+            OutputFormat::Json => {
+                if state.wrote_any {
+                    state.writer.write_all(b",\n")?;
+                }
+                let value = serde_json::json!({
+                    "path": entry.path.to_string_lossy(),
+                    "size_bytes": entry.size_bytes,
+                    "language": language_for(entry.path),
+                    "content": entry.content,
+                });
+                serde_json::to_writer(&mut state.writer, &value)?;
+            }
+        }
+
+        state.wrote_any = true;
+        state.total_files += 1;
+        state.total_bytes += entry.size_bytes;
+
+        Ok(())
+    }
+
+    /// Flush the writer and, for JSON, close out the document with a summary object
+    pub fn finish(self, skipped_files: u64) -> Result<()> {
+        let mut state = self.state.into_inner().unwrap();
+
+        if self.format == OutputFormat::Json {
+            state.writer.write_all(b"\n  ],\n")?;
+            let summary = serde_json::json!({
+                "total_files": state.total_files,
+                "total_bytes": state.total_bytes,
+                "skipped_files": skipped_files,
+            });
+            state.writer.write_all(b"  \"summary\": ")?;
+            serde_json::to_writer(&mut state.writer, &summary)?;
+            state.writer.write_all(b"\n}\n")?;
+        }
+
+        state.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Backtick fence long enough that it can't be closed early by a run of
+/// backticks already present in `content`
+fn fence_for(content: &str) -> String {
+    let longest_run = content
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+
+    "`".repeat((longest_run + 1).max(3))
+}
+
+/// Best-effort language tag for fenced code blocks / JSON "language" field
+fn language_for(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    match extension.as_str() {
+        "rs" => "rust",
+        "py" => "python",
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "jsx",
+        "ts" => "typescript",
+        "tsx" => "tsx",
+        "go" => "go",
+        "rb" => "ruby",
+        "java" => "java",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "cxx" | "hpp" => "cpp",
+        "cs" => "csharp",
+        "php" => "php",
+        "sh" | "bash" => "bash",
+        "yaml" | "yml" => "yaml",
+        "json" => "json",
+        "toml" => "toml",
+        "md" => "markdown",
+        "html" => "html",
+        "css" => "css",
+        "sql" => "sql",
+        _ => "",
+    }
+}
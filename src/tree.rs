@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::utils::format_file_size;
+
+enum Node {
+    Dir(BTreeMap<String, Node>),
+    File(u64),
+}
+
+/// Render a `tree`-style listing of `files` — relative paths paired with their
+/// size, already narrowed down to the ones that survived the binary/threshold
+/// filter — with directories sorted before files and each level alphabetical,
+/// followed by aggregate stats (total files, total bytes, counts per top-level
+/// directory).
+pub fn render(files: &[(impl AsRef<Path>, u64)]) -> String {
+    let mut root: BTreeMap<String, Node> = BTreeMap::new();
+    let mut total_bytes = 0u64;
+    let mut per_top_dir: BTreeMap<String, usize> = BTreeMap::new();
+
+    for (relative_path, size) in files {
+        let relative = relative_path.as_ref();
+        total_bytes += *size;
+
+        let mut components: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        if components.is_empty() {
+            continue;
+        }
+
+        if components.len() > 1 {
+            *per_top_dir.entry(components[0].clone()).or_insert(0) += 1;
+        }
+
+        let file_name = components.pop().unwrap();
+        let mut cursor = &mut root;
+        for part in components {
+            cursor = match cursor
+                .entry(part)
+                .or_insert_with(|| Node::Dir(BTreeMap::new()))
+            {
+                Node::Dir(children) => children,
+                Node::File(_) => unreachable!("path component collides with a file entry"),
+            };
+        }
+        cursor.insert(file_name, Node::File(*size));
+    }
+
+    let mut out = String::new();
+    out.push_str("Repository structure:\n\n");
+    render_children(&root, "", &mut out);
+
+    out.push('\n');
+    out.push_str(&format!("Total files: {}\n", files.len()));
+    out.push_str(&format!("Total size: {}\n", format_file_size(total_bytes)));
+    out.push_str("Files per top-level directory:\n");
+    for (dir, count) in &per_top_dir {
+        out.push_str(&format!("  {}: {}\n", dir, count));
+    }
+
+    out
+}
+
+fn render_children(children: &BTreeMap<String, Node>, prefix: &str, out: &mut String) {
+    let mut entries: Vec<(&String, &Node)> = children.iter().collect();
+    entries.sort_by(|(name_a, node_a), (name_b, node_b)| {
+        let a_is_dir = matches!(node_a, Node::Dir(_));
+        let b_is_dir = matches!(node_b, Node::Dir(_));
+        b_is_dir.cmp(&a_is_dir).then_with(|| name_a.cmp(name_b))
+    });
+
+    let last_index = entries.len().saturating_sub(1);
+    for (i, (name, node)) in entries.into_iter().enumerate() {
+        let is_last = i == last_index;
+        let connector = if is_last { "└── " } else { "├── " };
+
+        match node {
+            Node::Dir(children) => {
+                out.push_str(&format!("{}{}{}\n", prefix, connector, name));
+                let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+                render_children(children, &child_prefix, out);
+            }
+            Node::File(size) => {
+                out.push_str(&format!(
+                    "{}{}{} ({})\n",
+                    prefix,
+                    connector,
+                    name,
+                    format_file_size(*size)
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn directories_sort_before_files_at_each_level() {
+        let files: Vec<(PathBuf, u64)> = vec![
+            (PathBuf::from("README.md"), 10),
+            (PathBuf::from("src/main.rs"), 20),
+            (PathBuf::from("a.txt"), 5),
+        ];
+
+        let rendered = render(&files);
+        let src_pos = rendered.find("src").unwrap();
+        let readme_pos = rendered.find("README.md").unwrap();
+        let a_txt_pos = rendered.find("a.txt").unwrap();
+
+        assert!(src_pos < readme_pos);
+        assert!(src_pos < a_txt_pos);
+    }
+
+    #[test]
+    fn aggregate_stats_reflect_only_the_given_files() {
+        let files: Vec<(PathBuf, u64)> = vec![
+            (PathBuf::from("src/main.rs"), 100),
+            (PathBuf::from("src/lib.rs"), 50),
+            (PathBuf::from("docs/readme.md"), 25),
+        ];
+
+        let rendered = render(&files);
+
+        assert!(rendered.contains("Total files: 3"));
+        assert!(rendered.contains("src: 2"));
+        assert!(rendered.contains("docs: 1"));
+    }
+}
@@ -1,5 +1,7 @@
 use anyhow::{Result, anyhow};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use memmap2::Mmap;
+use std::collections::HashSet;
 use std::fs;
 use std::io::Read;
 use std::path::Path;
@@ -66,40 +68,165 @@ pub fn format_file_size(size: u64) -> String {
 //     }
 // }
 
-/// Optimized binary file detection - checks only first 512 bytes
-pub fn is_binary_file(path: &Path) -> Result<bool> {
+/// Outcome of opening a file once to gather its size and sniff its header
+#[derive(Clone)]
+pub struct FileInspection {
+    pub size: u64,
+    pub is_binary: bool,
+    pub detected_type: Option<String>,
+}
+
+/// Open `path` once, stat it through the handle, and sniff its first 512 bytes.
+/// Returns the inspection result along with the sniff buffer and still-open file
+/// so the caller can continue reading from where the sniff left off without a
+/// second open.
+pub fn inspect_file(path: &Path) -> Result<(FileInspection, Vec<u8>, fs::File)> {
     let mut file = fs::File::open(path)?;
-    let mut buffer = [0; 512];
+    let size = file.metadata()?.len();
+
+    let mut buffer = [0u8; 512];
     let bytes_read = file.read(&mut buffer)?;
+    let sniff_buf = buffer[..bytes_read].to_vec();
+
+    let (is_binary, detected_type) = if let Some(kind) = infer::get(&sniff_buf) {
+        (true, Some(kind.mime_type().to_string()))
+    } else {
+        (sniff_buf.contains(&0), None)
+    };
 
-    // Check for null bytes (common indicator of binary files)
-    Ok(buffer[..bytes_read].contains(&0))
+    Ok((
+        FileInspection {
+            size,
+            is_binary,
+            detected_type,
+        },
+        sniff_buf,
+        file,
+    ))
 }
 
-/// Efficiently read file content based on size
-pub fn read_file_content(path: &Path, file_size: u64) -> Result<String> {
-    // For small files, use regular read
-    if file_size < 1024 * 1024 {
-        // < 1MB
-        return Ok(fs::read_to_string(path)?);
+/// Read the remainder of a file after `inspect_file` already consumed its sniff
+/// buffer, continuing on the same handle instead of reopening it. Large files are
+/// memory-mapped; smaller ones are read to the end and appended to the sniff buffer.
+pub fn finish_reading(mut file: fs::File, sniff_buf: Vec<u8>, total_size: u64) -> Result<String> {
+    if total_size >= 1024 * 1024 {
+        let mmap = unsafe { Mmap::map(&file)? };
+        return match std::str::from_utf8(&mmap) {
+            Ok(content) => Ok(content.to_string()),
+            Err(_) => Ok(String::from_utf8_lossy(&mmap).to_string()),
+        };
     }
 
-    // For larger files, use memory mapping for better performance
-    let file = fs::File::open(path)?;
-    let mmap = unsafe { Mmap::map(&file)? };
+    let mut rest = Vec::new();
+    file.read_to_end(&mut rest)?;
+
+    let mut bytes = sniff_buf;
+    bytes.extend_from_slice(&rest);
+
+    Ok(String::from_utf8(bytes)?)
+}
+
+/// Read the full contents of a file whose size and binary-ness are already known
+/// (e.g. from an earlier `inspect_file` pass), without re-sniffing its header
+pub fn read_file_content(path: &Path, size: u64) -> Result<String> {
+    let mut file = fs::File::open(path)?;
 
-    // Convert to string, handling potential UTF-8 errors
-    match std::str::from_utf8(&mmap) {
-        Ok(content) => Ok(content.to_string()),
-        Err(_) => Ok(String::from_utf8_lossy(&mmap).to_string()),
+    if size >= 1024 * 1024 {
+        let mmap = unsafe { Mmap::map(&file)? };
+        return match std::str::from_utf8(&mmap) {
+            Ok(content) => Ok(content.to_string()),
+            Err(_) => Ok(String::from_utf8_lossy(&mmap).to_string()),
+        };
     }
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8(bytes)?)
 }
 
-/// Check if a file should be excluded based on its path
-pub fn should_exclude_file(path: &Path) -> bool {
+/// User-supplied extension/glob filters, compiled once and reused for every file check
+pub struct FileFilters {
+    include_ext: Option<HashSet<String>>,
+    exclude_ext: HashSet<String>,
+    exclude_glob: Option<GlobSet>,
+}
+
+impl FileFilters {
+    pub fn new(include_ext: &[String], exclude_ext: &[String], exclude_glob: &[String]) -> Result<Self> {
+        let normalize = |exts: &[String]| -> HashSet<String> {
+            exts.iter()
+                .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+                .filter(|e| !e.is_empty())
+                .collect()
+        };
+
+        let include_ext = normalize(include_ext);
+        let include_ext = if include_ext.is_empty() {
+            None
+        } else {
+            Some(include_ext)
+        };
+
+        let exclude_ext = normalize(exclude_ext);
+
+        let exclude_glob = if exclude_glob.is_empty() {
+            None
+        } else {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in exclude_glob {
+                builder.add(Glob::new(pattern.trim())?);
+            }
+            Some(builder.build()?)
+        };
+
+        Ok(Self {
+            include_ext,
+            exclude_ext,
+            exclude_glob,
+        })
+    }
+
+    /// Returns true if the user passed `--include-ext`, meaning they've explicitly
+    /// opted specific extensions back in even if the defaults would skip them
+    pub fn has_include_ext(&self) -> bool {
+        self.include_ext.is_some()
+    }
+
+    /// Returns true if the file should be excluded by the user's extension/glob filters
+    pub fn should_exclude(&self, path: &Path) -> bool {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase());
+
+        if let Some(include_ext) = &self.include_ext {
+            match &extension {
+                Some(ext) if include_ext.contains(ext) => {}
+                _ => return true,
+            }
+        }
+
+        if let Some(ext) = &extension {
+            if self.exclude_ext.contains(ext) {
+                return true;
+            }
+        }
+
+        if let Some(exclude_glob) = &self.exclude_glob {
+            if exclude_glob.is_match(path) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// Check if a file lives inside a directory that shouldn't be processed
+/// (build output, VCS internals, dependency caches, etc.)
+pub fn is_excluded_directory(path: &Path) -> bool {
     let path_str = path.to_string_lossy().to_lowercase();
 
-    // Exclude common directories that shouldn't be processed
     const EXCLUDED_DIRS: &[&str] = &[
         "node_modules",
         ".git",
@@ -120,7 +247,6 @@ pub fn should_exclude_file(path: &Path) -> bool {
         "cmake-build-release",
     ];
 
-    // Check if any part of the path contains excluded directories
     for excluded in EXCLUDED_DIRS {
         if path_str.contains(&format!("/{}/", excluded))
             || path_str.starts_with(&format!("{}/", excluded))
@@ -131,18 +257,108 @@ pub fn should_exclude_file(path: &Path) -> bool {
         }
     }
 
-    // Exclude common binary file extensions
-    if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
-        const BINARY_EXTENSIONS: &[&str] = &[
-            "exe", "dll", "so", "dylib", "bin", "obj", "o", "a", "lib", "jpg", "jpeg", "png",
-            "gif", "bmp", "ico", "svg", "mp3", "mp4", "avi", "mov", "wav", "flac", "zip", "tar",
-            "gz", "rar", "7z", "bz2", "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx",
-        ];
+    false
+}
 
-        if BINARY_EXTENSIONS.contains(&extension.to_lowercase().as_str()) {
-            return true;
-        }
+/// Check if a file's extension is one of the common binary formats we skip by default
+pub fn is_excluded_extension(path: &Path) -> bool {
+    const BINARY_EXTENSIONS: &[&str] = &[
+        "exe", "dll", "so", "dylib", "bin", "obj", "o", "a", "lib", "jpg", "jpeg", "png", "gif",
+        "bmp", "ico", "svg", "mp3", "mp4", "avi", "mov", "wav", "flac", "zip", "tar", "gz", "rar",
+        "7z", "bz2", "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx",
+    ];
+
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| BINARY_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Check if a file should be excluded based on its path: either it lives inside an
+/// excluded directory, or its extension is a common binary format
+pub fn should_exclude_file(path: &Path) -> bool {
+    is_excluded_directory(path) || is_excluded_extension(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn include_ext_rejects_everything_else() {
+        let filters = FileFilters::new(&["rs".to_string(), "toml".to_string()], &[], &[]).unwrap();
+
+        assert!(!filters.should_exclude(&PathBuf::from("src/main.rs")));
+        assert!(!filters.should_exclude(&PathBuf::from("Cargo.toml")));
+        assert!(filters.should_exclude(&PathBuf::from("README.md")));
     }
 
-    false
+    #[test]
+    fn exclude_ext_wins_even_when_also_included() {
+        let filters = FileFilters::new(
+            &["rs".to_string(), "lock".to_string()],
+            &["lock".to_string()],
+            &[],
+        )
+        .unwrap();
+
+        assert!(!filters.should_exclude(&PathBuf::from("src/main.rs")));
+        assert!(filters.should_exclude(&PathBuf::from("Cargo.lock")));
+    }
+
+    #[test]
+    fn exclude_glob_matches_patterns() {
+        let filters = FileFilters::new(&[], &[], &["*.min.js".to_string()]).unwrap();
+
+        assert!(filters.should_exclude(&PathBuf::from("dist/app.min.js")));
+        assert!(!filters.should_exclude(&PathBuf::from("dist/app.js")));
+    }
+
+    #[test]
+    fn no_filters_excludes_nothing() {
+        let filters = FileFilters::new(&[], &[], &[]).unwrap();
+
+        assert!(!filters.should_exclude(&PathBuf::from("anything.xyz")));
+    }
+
+    fn write_temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("textify_test_{}_{}", std::process::id(), name));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn inspect_file_detects_magic_bytes_as_binary() {
+        let png_header = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+        let path = write_temp_file("png_header", &png_header);
+
+        let (inspection, _sniff_buf, _file) = inspect_file(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert!(inspection.is_binary);
+        assert_eq!(inspection.detected_type.as_deref(), Some("image/png"));
+    }
+
+    #[test]
+    fn inspect_file_falls_back_to_nul_scan_for_unrecognized_binary() {
+        let bytes = [b'a', b'b', 0, b'c'];
+        let path = write_temp_file("nul_fallback", &bytes);
+
+        let (inspection, _sniff_buf, _file) = inspect_file(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert!(inspection.is_binary);
+        assert!(inspection.detected_type.is_none());
+    }
+
+    #[test]
+    fn inspect_file_treats_plain_text_as_not_binary() {
+        let path = write_temp_file("plain_text", b"fn main() {}\n");
+
+        let (inspection, _sniff_buf, _file) = inspect_file(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert!(!inspection.is_binary);
+        assert!(inspection.detected_type.is_none());
+    }
 }